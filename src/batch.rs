@@ -0,0 +1,111 @@
+/// A single operation recorded in a `WriteBatch`.
+pub(crate) enum BatchOp {
+    Set { key: Vec<u8>, value: Vec<u8> },
+    Delete { key: Vec<u8> },
+}
+
+/// WriteBatch accumulates a sequence of set/delete operations so they can be
+/// applied atomically: `WAL::apply_batch` appends the whole batch to the WAL
+/// as one contiguous record before any of it is replayed into the MemTable,
+/// so a crash either sees all of the batch's writes or none of them.
+#[derive(Default)]
+pub struct WriteBatch {
+    ops: Vec<BatchOp>,
+}
+
+impl WriteBatch {
+    /// Create an empty batch.
+    pub fn new() -> WriteBatch {
+        WriteBatch::default()
+    }
+
+    /// Queue a set operation.
+    pub fn set(&mut self, key: &[u8], value: &[u8]) {
+        self.ops.push(BatchOp::Set {
+            key: key.to_owned(),
+            value: value.to_owned(),
+        });
+    }
+
+    /// Queue a delete operation.
+    pub fn delete(&mut self, key: &[u8]) {
+        self.ops.push(BatchOp::Delete {
+            key: key.to_owned(),
+        });
+    }
+
+    /// Number of operations queued in this batch.
+    pub fn len(&self) -> usize {
+        self.ops.len()
+    }
+
+    /// Check if the batch has no queued operations.
+    pub fn is_empty(&self) -> bool {
+        self.ops.is_empty()
+    }
+
+    pub(crate) fn ops(&self) -> &[BatchOp] {
+        &self.ops
+    }
+}
+
+impl BatchOp {
+    pub(crate) fn key(&self) -> &[u8] {
+        match self {
+            BatchOp::Set { key, .. } => key,
+            BatchOp::Delete { key } => key,
+        }
+    }
+
+    pub(crate) fn value(&self) -> Option<&[u8]> {
+        match self {
+            BatchOp::Set { value, .. } => Some(value),
+            BatchOp::Delete { .. } => None,
+        }
+    }
+
+    pub(crate) fn deleted(&self) -> bool {
+        matches!(self, BatchOp::Delete { .. })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new_batch_is_empty() {
+        let batch = WriteBatch::new();
+        assert!(batch.is_empty());
+        assert_eq!(batch.len(), 0);
+    }
+
+    #[test]
+    fn test_len_counts_sets_and_deletes() {
+        let mut batch = WriteBatch::new();
+        batch.set(b"alice", b"product manager");
+        batch.delete(b"bob");
+        batch.set(b"carl", b"kernel engineer");
+
+        assert!(!batch.is_empty());
+        assert_eq!(batch.len(), 3);
+    }
+
+    #[test]
+    fn test_ops_preserve_order_and_kind() {
+        let mut batch = WriteBatch::new();
+        batch.set(b"alice", b"product manager");
+        batch.delete(b"bob");
+
+        let ops = batch.ops();
+        assert_eq!(ops.len(), 2);
+
+        assert_eq!(ops[0].key(), b"alice");
+        assert_eq!(ops[0].value(), Some(b"product manager".as_slice()));
+        assert!(!ops[0].deleted());
+
+        assert_eq!(ops[1].key(), b"bob");
+        assert_eq!(ops[1].value(), None);
+        assert!(ops[1].deleted());
+    }
+}
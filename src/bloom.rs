@@ -0,0 +1,151 @@
+#![allow(dead_code)]
+
+/// A Bloom filter is a space-efficient probabilistic set: membership tests
+/// never false-negative but can false-positive at a tunable rate. SSTables
+/// use one per table to skip a disk seek for keys that are definitely absent.
+///
+/// Bits are addressed via double hashing, `h_i(key) = h1(key) + i*h2(key) mod m`,
+/// which only requires two independent hashes of the key no matter how many
+/// hash functions `k` the filter needs.
+pub struct BloomFilter {
+    bits: Vec<u64>,
+    num_bits: usize,
+    num_hashes: usize,
+}
+
+impl BloomFilter {
+    /// Build an empty filter sized for `num_entries` keys at the given target
+    /// false-positive rate (e.g. `0.01` for 1%).
+    pub fn new(num_entries: usize, false_positive_rate: f64) -> BloomFilter {
+        let num_entries = num_entries.max(1);
+        let num_bits = optimal_num_bits(num_entries, false_positive_rate);
+        let num_hashes = optimal_num_hashes(num_bits, num_entries);
+
+        BloomFilter {
+            bits: vec![0; num_bits.div_ceil(64)],
+            num_bits,
+            num_hashes,
+        }
+    }
+
+    /// Record `key` as a member of the set.
+    pub fn insert(&mut self, key: &[u8]) {
+        for idx in bit_indexes(key, self.num_bits, self.num_hashes) {
+            self.bits[idx / 64] |= 1 << (idx % 64);
+        }
+    }
+
+    /// Test whether `key` may be a member of the set. A `false` result means
+    /// the key is definitely absent, a `true` result means it is probably
+    /// present (subject to the configured false-positive rate).
+    pub fn contains(&self, key: &[u8]) -> bool {
+        bit_indexes(key, self.num_bits, self.num_hashes)
+            .all(|idx| self.bits[idx / 64] & (1 << (idx % 64)) != 0)
+    }
+
+    /// Serialize the filter as `num_bits (8B) | num_hashes (8B) | bit array`,
+    /// suitable for embedding in an SSTable's META BLOCK.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(16 + self.bits.len() * 8);
+        buf.extend_from_slice(&(self.num_bits as u64).to_le_bytes());
+        buf.extend_from_slice(&(self.num_hashes as u64).to_le_bytes());
+        for word in &self.bits {
+            buf.extend_from_slice(&word.to_le_bytes());
+        }
+        buf
+    }
+
+    /// Deserialize a filter previously written by `to_bytes`.
+    pub fn from_bytes(bytes: &[u8]) -> BloomFilter {
+        let num_bits = u64::from_le_bytes(bytes[0..8].try_into().unwrap()) as usize;
+        let num_hashes = u64::from_le_bytes(bytes[8..16].try_into().unwrap()) as usize;
+        let bits = bytes[16..]
+            .chunks_exact(8)
+            .map(|chunk| u64::from_le_bytes(chunk.try_into().unwrap()))
+            .collect();
+
+        BloomFilter {
+            bits,
+            num_bits,
+            num_hashes,
+        }
+    }
+}
+
+/// Derive the `num_hashes` bit positions for `key` via double hashing,
+/// `h_i(key) = h1(key) + i*h2(key) mod m`, from two independent 64-bit hashes.
+fn bit_indexes(key: &[u8], num_bits: usize, num_hashes: usize) -> impl Iterator<Item = usize> {
+    let h1 = hash64(key, 0);
+    let h2 = hash64(key, h1);
+    (0..num_hashes).map(move |i| (h1.wrapping_add((i as u64).wrapping_mul(h2)) as usize) % num_bits)
+}
+
+/// m = ceil(-(n * ln(p)) / (ln 2)^2)
+fn optimal_num_bits(num_entries: usize, false_positive_rate: f64) -> usize {
+    let n = num_entries as f64;
+    let m = -(n * false_positive_rate.ln()) / (std::f64::consts::LN_2 * std::f64::consts::LN_2);
+    (m.ceil() as usize).max(64)
+}
+
+/// k = round((m/n) * ln 2)
+fn optimal_num_hashes(num_bits: usize, num_entries: usize) -> usize {
+    let k = (num_bits as f64 / num_entries as f64) * std::f64::consts::LN_2;
+    (k.round() as usize).max(1)
+}
+
+/// FNV-1a 64-bit hash, seeded so the filter can derive two independent
+/// hashes of the same key by hashing once with seed 0 and once with the
+/// first hash as the seed.
+fn hash64(bytes: &[u8], seed: u64) -> u64 {
+    const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+    const FNV_PRIME: u64 = 0x100000001b3;
+
+    let mut hash = FNV_OFFSET_BASIS ^ seed;
+    for &byte in bytes {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(FNV_PRIME);
+    }
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_contains_inserted_keys() {
+        let mut filter = BloomFilter::new(100, 0.01);
+        let keys: Vec<&[u8]> = vec![b"alice", b"bob", b"carl"];
+        for key in &keys {
+            filter.insert(key);
+        }
+
+        for key in &keys {
+            assert!(filter.contains(key));
+        }
+    }
+
+    #[test]
+    fn test_roundtrip_bytes() {
+        let mut filter = BloomFilter::new(10, 0.01);
+        filter.insert(b"alice");
+
+        let bytes = filter.to_bytes();
+        let restored = BloomFilter::from_bytes(&bytes);
+        assert!(restored.contains(b"alice"));
+    }
+
+    #[test]
+    fn test_false_positive_rate_is_reasonable() {
+        let mut filter = BloomFilter::new(1000, 0.01);
+        for i in 0..1000u32 {
+            filter.insert(&i.to_le_bytes());
+        }
+
+        let false_positives = (1000..11000u32)
+            .filter(|i| filter.contains(&i.to_le_bytes()))
+            .count();
+        // Generous bound, this is a sanity check, not a precise statistical test.
+        assert!(false_positives < 500);
+    }
+}
@@ -0,0 +1,202 @@
+#![allow(dead_code)]
+use std::{
+    cmp::Ordering,
+    collections::BinaryHeap,
+    io,
+    path::Path,
+};
+
+use crate::{
+    memtable::{MemTable, MemTableEntry},
+    sstable::{SSTable, SSTABLE_MIN_SIZE},
+};
+
+/// Number of level-0 SSTables that triggers a compaction into level 1.
+///
+/// Level 0 is special-cased because its tables are flushed straight from
+/// MemTables and so can overlap in key range; every other level is kept
+/// non-overlapping by compacting it as a whole.
+pub const LEVEL0_COMPACTION_THRESHOLD: usize = 4;
+
+/// Level groups the SSTables that live at a given depth in the LSM tree.
+/// Level 0 holds freshly flushed, possibly overlapping tables; compacting
+/// a level merges it with the level below and produces a fresh set of
+/// tables for the next level up.
+pub struct Level {
+    pub level: usize,
+    pub tables: Vec<SSTable>,
+}
+
+impl Level {
+    pub fn new(level: usize) -> Level {
+        Level {
+            level,
+            tables: Vec::new(),
+        }
+    }
+
+    /// Create a `Level` already populated with `tables` (e.g. reopened from
+    /// disk), rather than starting empty.
+    pub fn with_tables(level: usize, tables: Vec<SSTable>) -> Level {
+        Level { level, tables }
+    }
+
+    /// Whether this level has accumulated enough tables to warrant a
+    /// compaction into the next level up.
+    pub fn should_compact(&self) -> bool {
+        self.level == 0 && self.tables.len() > LEVEL0_COMPACTION_THRESHOLD
+    }
+}
+
+/// Merge `inputs` into a fresh run of SSTables written to `dir`.
+///
+/// Performs a k-way merge over the inputs' sorted record streams keyed on
+/// `(key, reverse timestamp)`, so that when multiple inputs hold the same
+/// key only the highest-timestamp record survives. When `is_bottommost` is
+/// set, a surviving tombstone is dropped rather than written out, since no
+/// older version of the key can exist below the bottom level. The merged
+/// stream is split into fresh SSTables of `SSTABLE_MIN_SIZE` records each.
+pub fn compact(dir: &Path, inputs: &[SSTable], is_bottommost: bool) -> io::Result<Vec<SSTable>> {
+    let mut heap = BinaryHeap::new();
+    let mut iters: Vec<_> = inputs.iter().map(|table| table.iter()).collect::<io::Result<_>>()?;
+
+    for (source, iter) in iters.iter_mut().enumerate() {
+        if let Some(entry) = iter.next() {
+            heap.push(HeapEntry { entry, source });
+        }
+    }
+
+    let mut outputs = Vec::new();
+    let mut batch = MemTable::new();
+    let mut last_key: Option<Vec<u8>> = None;
+
+    while let Some(HeapEntry { entry, source }) = heap.pop() {
+        if let Some(next) = iters[source].next() {
+            heap.push(HeapEntry {
+                entry: next,
+                source,
+            });
+        }
+
+        // Duplicate keys across inputs surface consecutively (highest
+        // timestamp first, by HeapEntry's Ord), skip every version but
+        // the first we see for a given key.
+        if last_key.as_deref() == Some(entry.key.as_slice()) {
+            continue;
+        }
+        last_key = Some(entry.key.clone());
+
+        if entry.deleted && is_bottommost {
+            continue;
+        }
+
+        if entry.deleted {
+            batch.delete(&entry.key, entry.timestamp);
+        } else {
+            batch.set(&entry.key, entry.value.as_deref().unwrap_or_default(), entry.timestamp);
+        }
+
+        if batch.len() >= SSTABLE_MIN_SIZE {
+            outputs.push(SSTable::flush_from_memtable(dir, &batch)?);
+            batch = MemTable::new();
+        }
+    }
+
+    if !batch.is_empty() {
+        outputs.push(SSTable::flush_from_memtable(dir, &batch)?);
+    }
+
+    Ok(outputs)
+}
+
+/// Wraps a merge candidate so `BinaryHeap` (a max-heap) pops records in
+/// ascending key order, breaking ties on a shared key in favor of the
+/// highest timestamp so the newest version of a key is kept.
+struct HeapEntry {
+    entry: MemTableEntry,
+    source: usize,
+}
+
+impl PartialEq for HeapEntry {
+    fn eq(&self, other: &Self) -> bool {
+        self.entry.key == other.entry.key && self.entry.timestamp == other.entry.timestamp
+    }
+}
+
+impl Eq for HeapEntry {}
+
+impl PartialOrd for HeapEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for HeapEntry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .entry
+            .key
+            .cmp(&self.entry.key)
+            .then_with(|| self.entry.timestamp.cmp(&other.entry.timestamp))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{get_current_timestamp, temp_dir};
+
+    #[test]
+    fn test_compact_keeps_latest_version() {
+        let dir = temp_dir("compaction-latest");
+
+        let mut older = MemTable::new();
+        older.set(b"alice", b"v1", 100);
+        let older_table = SSTable::flush_from_memtable(&dir, &older).unwrap();
+
+        let mut newer = MemTable::new();
+        newer.set(b"alice", b"v2", 200);
+        let newer_table = SSTable::flush_from_memtable(&dir, &newer).unwrap();
+
+        let outputs = compact(&dir, &[older_table, newer_table], false).unwrap();
+        assert_eq!(outputs.len(), 1);
+
+        let mut merged = outputs.into_iter().next().unwrap();
+        let entry = merged.get(b"alice").unwrap().unwrap();
+        assert_eq!(entry.value.unwrap(), b"v2");
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_compact_drops_tombstones_at_bottommost_level() {
+        let dir = temp_dir("compaction-tombstone");
+
+        let mut older = MemTable::new();
+        older.set(b"alice", b"v1", 100);
+        let older_table = SSTable::flush_from_memtable(&dir, &older).unwrap();
+
+        let mut newer = MemTable::new();
+        newer.delete(b"alice", 200);
+        let newer_table = SSTable::flush_from_memtable(&dir, &newer).unwrap();
+
+        let outputs = compact(&dir, &[older_table, newer_table], true).unwrap();
+        assert!(outputs.is_empty());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_level_should_compact_threshold() {
+        let dir = temp_dir("compaction-level");
+        let mut level = Level::new(0);
+        for i in 0..=LEVEL0_COMPACTION_THRESHOLD {
+            let mut memtable = MemTable::new();
+            memtable.set(format!("key-{i}").as_bytes(), b"value", get_current_timestamp());
+            level.tables.push(SSTable::flush_from_memtable(&dir, &memtable).unwrap());
+        }
+        assert!(level.should_compact());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
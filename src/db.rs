@@ -0,0 +1,350 @@
+use std::{
+    fs::{create_dir_all, remove_file},
+    io,
+    ops::Bound,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    batch::WriteBatch,
+    compaction::{compact, Level},
+    files_with_ext, get_current_timestamp,
+    memtable::{MemTable, MEM_TABLE_SIZE},
+    scan::Scan,
+    sstable::SSTable,
+    wal::WAL,
+};
+
+/// Directory an on-disk level's SSTables live under, so the level a table
+/// belongs to survives a restart instead of being re-derived (and lost) from
+/// a flat pool of `.sst` files.
+fn level_dir(dir: &Path, level: usize) -> PathBuf {
+    dir.join(format!("level-{level}"))
+}
+
+/// Open every SSTable persisted under `dir`'s directory for `level`,
+/// creating that directory if this is a fresh Keg, newest table first so
+/// `get` searches recent tables before older ones.
+fn load_level_tables(dir: &Path, level: usize) -> io::Result<Vec<SSTable>> {
+    let dir = level_dir(dir, level);
+    create_dir_all(&dir)?;
+
+    let mut files = files_with_ext(&dir, "sst");
+    // SSTable files are named by creation timestamp, oldest first.
+    files.sort();
+    let mut tables = files
+        .iter()
+        .map(|path| SSTable::open(path))
+        .collect::<io::Result<Vec<_>>>()?;
+    tables.reverse();
+
+    Ok(tables)
+}
+
+/// Keg is the top-level storage engine, wiring the `MemTable`, its backing
+/// `WAL`, and the on-disk `SSTable`s into a single read/write path.
+///
+/// Writes append to the WAL, then update the live MemTable. Once the
+/// MemTable's size reaches `MEM_TABLE_SIZE` it is frozen and flushed to a
+/// new level-0 SSTable, and a fresh MemTable + WAL take over. Once level 0
+/// accumulates more tables than `LEVEL0_COMPACTION_THRESHOLD`, it is merged
+/// into level 1 (this engine's bottommost level), which drops tombstones
+/// and collapses duplicate keys to reclaim space.
+///
+/// Reads consult the live MemTable first, then level 0, then level 1, each
+/// from newest to oldest, honoring tombstones so a deleted key returns
+/// `None` even if an older SSTable still holds a value for it.
+pub struct Keg {
+    dir: PathBuf,
+    memtable: MemTable,
+    wal: WAL,
+    // `levels[0]` is level 0, `levels[1]` the bottommost level; within a
+    // level, newest SSTable first, so `get` can stop at the first match.
+    levels: Vec<Level>,
+}
+
+impl Keg {
+    /// Open (or create) a Keg rooted at `dir`, replaying any existing WALs
+    /// into a recovered MemTable and opening any existing SSTables.
+    pub fn open(dir: &Path) -> io::Result<Keg> {
+        create_dir_all(dir)?;
+
+        let (wal, memtable) = WAL::load_from_dir(dir)?;
+
+        let levels = vec![
+            Level::with_tables(0, load_level_tables(dir, 0)?),
+            Level::with_tables(1, load_level_tables(dir, 1)?),
+        ];
+
+        Ok(Keg {
+            dir: dir.to_owned(),
+            memtable,
+            wal,
+            levels,
+        })
+    }
+
+    /// Set a key-value pair, durably appending to the WAL before updating
+    /// the MemTable.
+    pub fn set(&mut self, key: &[u8], value: &[u8]) -> io::Result<()> {
+        let timestamp = get_current_timestamp();
+        self.wal.set(key, value, timestamp)?;
+        self.wal.flush()?;
+        self.memtable.set(key, value, timestamp);
+
+        self.maybe_flush_memtable()
+    }
+
+    /// Delete a key, durably appending a tombstone to the WAL before
+    /// updating the MemTable.
+    pub fn delete(&mut self, key: &[u8]) -> io::Result<()> {
+        let timestamp = get_current_timestamp();
+        self.wal.delete(key, timestamp)?;
+        self.wal.flush()?;
+        self.memtable.delete(key, timestamp);
+
+        self.maybe_flush_memtable()
+    }
+
+    /// Apply a `WriteBatch` atomically: the whole batch is durably appended
+    /// to the WAL as a single record before any of it updates the MemTable,
+    /// so a crash mid-batch replays either all of it or none of it.
+    pub fn apply_batch(&mut self, batch: &WriteBatch) -> io::Result<()> {
+        self.wal.apply_batch(batch, &mut self.memtable)?;
+
+        self.maybe_flush_memtable()
+    }
+
+    /// Get the value for `key`, or `None` if it is absent or was deleted.
+    pub fn get(&mut self, key: &[u8]) -> io::Result<Option<Vec<u8>>> {
+        if let Some(entry) = self.memtable.get(key) {
+            return Ok(entry.value.clone());
+        }
+
+        for level in self.levels.iter_mut() {
+            for sstable in level.tables.iter_mut() {
+                if let Some(entry) = sstable.get(key)? {
+                    return Ok(entry.value);
+                }
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Iterate live key-value pairs in ascending key order over `(lower, upper)`,
+    /// merging the live MemTable with every SSTable and suppressing shadowed
+    /// versions and deleted keys. Pass `Bound::Unbounded` on either side to
+    /// leave that end of the range open, and `Bound::Included`/`Bound::Excluded`
+    /// with a key to bound it; callers can resume a scan by starting the next
+    /// one's lower bound just past the last key they consumed.
+    pub fn scan(&self, lower: Bound<Vec<u8>>, upper: Bound<Vec<u8>>) -> io::Result<Scan<'_>> {
+        Scan::new(
+            &self.memtable,
+            self.levels.iter().flat_map(|level| level.tables.iter()),
+            lower,
+            upper,
+        )
+    }
+
+    /// Freeze and flush the live MemTable to a new level-0 SSTable once it
+    /// reaches `MEM_TABLE_SIZE`, retiring its WAL and starting fresh ones,
+    /// then compact level 0 into level 1 if it has grown past its threshold.
+    fn maybe_flush_memtable(&mut self) -> io::Result<()> {
+        if self.memtable.size() < MEM_TABLE_SIZE {
+            return Ok(());
+        }
+
+        let frozen = std::mem::take(&mut self.memtable);
+        let sstable = SSTable::flush_from_memtable(&level_dir(&self.dir, 0), &frozen)?;
+        self.levels[0].tables.insert(0, sstable);
+
+        let retired_wal_path = self.wal.path().to_owned();
+        self.wal = WAL::new(&self.dir)?;
+        remove_file(retired_wal_path)?;
+
+        self.maybe_compact_level0()
+    }
+
+    /// Merge level 0 into level 1 once level 0 holds more than
+    /// `LEVEL0_COMPACTION_THRESHOLD` tables. Level 1 is this engine's
+    /// bottommost level, so the merge also drops tombstones, and the
+    /// compacted-away input files are removed once their replacements are
+    /// durably written.
+    ///
+    /// `self.levels` is only mutated once `compact` has durably written its
+    /// replacement tables: on failure the original level-0/level-1 tables
+    /// are restored rather than left empty, so an error here doesn't make a
+    /// live `Keg` "forget" data it already had on disk.
+    fn maybe_compact_level0(&mut self) -> io::Result<()> {
+        if !self.levels[0].should_compact() {
+            return Ok(());
+        }
+
+        let level0 = std::mem::take(&mut self.levels[0].tables);
+        let mut level1 = std::mem::take(&mut self.levels[1].tables);
+        let split = level0.len();
+
+        let mut inputs = level0;
+        inputs.append(&mut level1);
+
+        let outputs = match compact(&level_dir(&self.dir, 1), &inputs, true) {
+            Ok(outputs) => outputs,
+            Err(e) => {
+                let level1 = inputs.split_off(split);
+                self.levels[0].tables = inputs;
+                self.levels[1].tables = level1;
+                return Err(e);
+            }
+        };
+
+        let retired_paths: Vec<PathBuf> = inputs.iter().map(|table| table.path().to_owned()).collect();
+        self.levels[1].tables = outputs;
+        for path in retired_paths {
+            // The merged outputs already replaced the inputs in `self.levels`,
+            // so a removal failure leaves an orphaned file on disk (which
+            // `open` loads again as a table of its level) rather than losing
+            // track of the (already-merged) data.
+            remove_file(path)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::temp_dir;
+
+    #[test]
+    fn test_set_get_delete() {
+        let dir = temp_dir("db-basic");
+        let mut keg = Keg::open(&dir).unwrap();
+
+        keg.set(b"alice", b"product manager").unwrap();
+        assert_eq!(keg.get(b"alice").unwrap(), Some(b"product manager".to_vec()));
+
+        keg.delete(b"alice").unwrap();
+        assert_eq!(keg.get(b"alice").unwrap(), None);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_apply_batch_sets_and_deletes() {
+        let dir = temp_dir("db-batch");
+        let mut keg = Keg::open(&dir).unwrap();
+
+        keg.set(b"alice", b"product manager").unwrap();
+
+        let mut batch = crate::batch::WriteBatch::new();
+        batch.set(b"bob", b"reliability engineer");
+        batch.delete(b"alice");
+        keg.apply_batch(&batch).unwrap();
+
+        assert_eq!(keg.get(b"alice").unwrap(), None);
+        assert_eq!(keg.get(b"bob").unwrap(), Some(b"reliability engineer".to_vec()));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_flush_to_sstable_and_reopen() {
+        let dir = temp_dir("db-flush");
+        {
+            let mut keg = Keg::open(&dir).unwrap();
+            // Large enough values to push the MemTable past MEM_TABLE_SIZE
+            // and trigger a flush to an SSTable.
+            let value = vec![0u8; 512];
+            for i in 0..16 {
+                keg.set(format!("key-{i}").as_bytes(), &value).unwrap();
+            }
+            assert_eq!(keg.get(b"key-0").unwrap(), Some(value.clone()));
+        }
+
+        let mut reopened = Keg::open(&dir).unwrap();
+        assert!(reopened.get(b"key-0").unwrap().is_some());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_level0_compacts_into_level1_past_threshold() {
+        let dir = temp_dir("db-compact");
+        let mut keg = Keg::open(&dir).unwrap();
+
+        // Large enough values to flush a new level-0 SSTable per batch of
+        // writes, and enough batches to push level 0 past
+        // `LEVEL0_COMPACTION_THRESHOLD` and trigger a compaction into
+        // level 1.
+        let value = vec![0u8; 512];
+        for batch in 0..5 {
+            for i in 0..16 {
+                keg.set(format!("batch-{batch}-key-{i}").as_bytes(), &value).unwrap();
+            }
+        }
+        keg.delete(b"batch-0-key-0").unwrap();
+        keg.set(b"final-flush-key", &value).unwrap();
+
+        assert!(keg.levels[0].tables.len() <= crate::compaction::LEVEL0_COMPACTION_THRESHOLD);
+        assert!(!keg.levels[1].tables.is_empty());
+        assert_eq!(keg.get(b"batch-0-key-0").unwrap(), None);
+        assert_eq!(keg.get(b"batch-1-key-1").unwrap(), Some(value));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_levels_survive_reopen() {
+        let dir = temp_dir("db-levels-reopen");
+        let level0_count;
+        let level1_count;
+        {
+            let mut keg = Keg::open(&dir).unwrap();
+
+            let value = vec![0u8; 512];
+            for batch in 0..5 {
+                for i in 0..16 {
+                    keg.set(format!("batch-{batch}-key-{i}").as_bytes(), &value).unwrap();
+                }
+            }
+            level0_count = keg.levels[0].tables.len();
+            level1_count = keg.levels[1].tables.len();
+            assert!(!keg.levels[1].tables.is_empty());
+        }
+
+        let reopened = Keg::open(&dir).unwrap();
+        assert_eq!(reopened.levels[0].tables.len(), level0_count);
+        assert_eq!(reopened.levels[1].tables.len(), level1_count);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_scan_merges_live_and_flushed_entries() {
+        let dir = temp_dir("db-scan");
+        let mut keg = Keg::open(&dir).unwrap();
+
+        let value = vec![0u8; 512];
+        for i in 0..16 {
+            keg.set(format!("key-{i:02}").as_bytes(), &value).unwrap();
+        }
+        keg.set(b"key-16", b"live").unwrap();
+        keg.delete(b"key-05").unwrap();
+
+        let keys: Vec<Vec<u8>> = keg
+            .scan(Bound::Unbounded, Bound::Unbounded)
+            .unwrap()
+            .map(|(key, _)| key)
+            .collect();
+
+        let expected: Vec<Vec<u8>> = (0..17)
+            .filter(|i| *i != 5)
+            .map(|i| format!("key-{i:02}").into_bytes())
+            .collect();
+        assert_eq!(keys, expected);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -5,29 +5,51 @@ use std::{
 };
 
 /// Public modules
+pub mod batch;
+pub mod bloom;
+pub mod compaction;
 pub mod crc32;
+pub mod db;
 pub mod memtable;
+pub mod scan;
 pub mod sstable;
 pub mod wal;
 
 fn get_current_timestamp() -> u128 {
+    // Nanosecond resolution, so SSTable/WAL filenames (and consecutive
+    // MemTable entry timestamps) derived from back-to-back calls don't
+    // collide the way millisecond resolution could under a fast flush path.
     SystemTime::now()
         .duration_since(UNIX_EPOCH)
         .unwrap()
-        .as_millis()
+        .as_nanos()
 }
-/// Gets the set of files with an extension for a given directory.
+/// Gets the set of files with an extension for a given directory. Entries
+/// with no extension (e.g. dotfiles) are skipped rather than treated as a
+/// mismatch, since `Keg::open` may be pointed at an arbitrary directory.
 fn files_with_ext(dir: &Path, ext: &str) -> Vec<PathBuf> {
     let mut files = Vec::new();
     for file in read_dir(dir).unwrap() {
         let path = file.unwrap().path();
-        if path.extension().unwrap() == ext {
+        if path.extension().and_then(|e| e.to_str()) == Some(ext) {
             files.push(path);
         }
     }
 
     files
 }
+
+/// Shared test fixture: a freshly created, uniquely named temp directory.
+/// Used by every module whose tests exercise on-disk state (WAL, SSTable,
+/// compaction, Keg), so the directory-naming and creation logic isn't
+/// reimplemented per module.
+#[cfg(test)]
+pub(crate) fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("keg-test-{name}-{}", get_current_timestamp()));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -37,4 +59,17 @@ mod tests {
         let result = 2 + 2;
         assert_eq!(result, 4);
     }
+
+    #[test]
+    fn test_files_with_ext_skips_extensionless_entries() {
+        let dir = temp_dir("lib-files-with-ext");
+
+        std::fs::write(dir.join("README"), b"").unwrap();
+        std::fs::write(dir.join("000001.sst"), b"").unwrap();
+
+        let files = files_with_ext(&dir, "sst");
+        assert_eq!(files, vec![dir.join("000001.sst")]);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
 }
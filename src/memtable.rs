@@ -13,6 +13,7 @@ pub struct MemTable {
     cap: usize,
 }
 
+#[derive(Clone)]
 pub struct MemTableEntry {
     pub key: Vec<u8>,
     pub value: Option<Vec<u8>>,
@@ -40,6 +41,12 @@ impl MemTable {
         self.entries.len()
     }
 
+    /// Approximate size in bytes of the entries currently held, compared
+    /// against `cap` to decide when to flush to an SSTable.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
     /// Check if the MemTable is empty.
     pub fn is_empty(&self) -> bool {
         self.len() == 0
@@ -0,0 +1,228 @@
+use std::{cmp::Ordering, collections::BinaryHeap, io, ops::Bound};
+
+use crate::{
+    memtable::{MemTable, MemTableEntry},
+    sstable::SSTable,
+};
+
+/// Scan performs a sorted range iteration over a MemTable and a set of
+/// SSTables, merging their (already sorted) record streams with a min-heap
+/// the same way `compaction::compact` merges SSTables, except it also
+/// filters to a key range, suppresses shadowed duplicates (keeping only the
+/// highest-timestamp version of a key), and skips tombstoned keys so
+/// callers only ever see live key-value pairs.
+///
+/// Each SSTable's DATA BLOCK is seeked to the range's lower bound via its
+/// sparse INDEX BLOCK, so a scan starting deep into a table doesn't pay for
+/// reading the records ahead of it.
+pub struct Scan<'a> {
+    sources: Vec<Box<dyn Iterator<Item = MemTableEntry> + 'a>>,
+    heap: BinaryHeap<HeapEntry>,
+    upper: Bound<Vec<u8>>,
+    last_key: Option<Vec<u8>>,
+}
+
+impl<'a> Scan<'a> {
+    /// Start a scan over `memtable` and `sstables` covering `(lower, upper)`.
+    /// `sstables` may come from more than one level (e.g. `Keg` flattening
+    /// its levels with `flat_map`), so it's taken as any iterator rather
+    /// than a contiguous slice.
+    pub fn new(
+        memtable: &'a MemTable,
+        sstables: impl IntoIterator<Item = &'a SSTable>,
+        lower: Bound<Vec<u8>>,
+        upper: Bound<Vec<u8>>,
+    ) -> io::Result<Scan<'a>> {
+        let mut sources: Vec<Box<dyn Iterator<Item = MemTableEntry> + 'a>> = Vec::new();
+
+        let memtable_lower = lower.clone();
+        sources.push(Box::new(
+            memtable
+                .entries()
+                .iter()
+                .filter(move |entry| at_or_after_lower(&memtable_lower, &entry.key))
+                .cloned(),
+        ));
+
+        let range_start: &[u8] = match &lower {
+            Bound::Included(key) | Bound::Excluded(key) => key,
+            Bound::Unbounded => &[],
+        };
+        for sstable in sstables {
+            let sstable_lower = lower.clone();
+            sources.push(Box::new(
+                sstable
+                    .range(range_start)?
+                    .filter(move |entry| at_or_after_lower(&sstable_lower, &entry.key)),
+            ));
+        }
+
+        let mut heap = BinaryHeap::new();
+        for (source, iter) in sources.iter_mut().enumerate() {
+            if let Some(entry) = iter.next() {
+                heap.push(HeapEntry { entry, source });
+            }
+        }
+
+        Ok(Scan {
+            sources,
+            heap,
+            upper,
+            last_key: None,
+        })
+    }
+}
+
+impl<'a> Iterator for Scan<'a> {
+    type Item = (Vec<u8>, Vec<u8>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let HeapEntry { entry, source } = self.heap.pop()?;
+            if let Some(next) = self.sources[source].next() {
+                self.heap.push(HeapEntry { entry: next, source });
+            }
+
+            // Duplicate keys across sources surface consecutively (highest
+            // timestamp first, by HeapEntry's Ord), skip every version but
+            // the first we see for a given key.
+            if self.last_key.as_deref() == Some(entry.key.as_slice()) {
+                continue;
+            }
+            self.last_key = Some(entry.key.clone());
+
+            // The heap yields keys in ascending order, so once one falls
+            // outside the upper bound every later key will too.
+            if !before_upper(&self.upper, &entry.key) {
+                self.heap.clear();
+                return None;
+            }
+
+            if entry.deleted {
+                continue;
+            }
+
+            return Some((entry.key.clone(), entry.value.clone().unwrap_or_default()));
+        }
+    }
+}
+
+fn at_or_after_lower(bound: &Bound<Vec<u8>>, key: &[u8]) -> bool {
+    match bound {
+        Bound::Unbounded => true,
+        Bound::Included(lower) => key >= lower.as_slice(),
+        Bound::Excluded(lower) => key > lower.as_slice(),
+    }
+}
+
+fn before_upper(bound: &Bound<Vec<u8>>, key: &[u8]) -> bool {
+    match bound {
+        Bound::Unbounded => true,
+        Bound::Included(upper) => key <= upper.as_slice(),
+        Bound::Excluded(upper) => key < upper.as_slice(),
+    }
+}
+
+/// Wraps a merge candidate so `BinaryHeap` (a max-heap) pops records in
+/// ascending key order, breaking ties on a shared key in favor of the
+/// highest timestamp so the newest version of a key is kept.
+struct HeapEntry {
+    entry: MemTableEntry,
+    source: usize,
+}
+
+impl PartialEq for HeapEntry {
+    fn eq(&self, other: &Self) -> bool {
+        self.entry.key == other.entry.key && self.entry.timestamp == other.entry.timestamp
+    }
+}
+
+impl Eq for HeapEntry {}
+
+impl PartialOrd for HeapEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for HeapEntry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .entry
+            .key
+            .cmp(&self.entry.key)
+            .then_with(|| self.entry.timestamp.cmp(&other.entry.timestamp))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::temp_dir;
+
+    #[test]
+    fn test_scan_merges_memtable_and_sstable_in_order() {
+        let dir = temp_dir("scan-merge");
+
+        let mut flushed = MemTable::new();
+        flushed.set(b"alice", b"v1", 100);
+        flushed.set(b"carl", b"v1", 100);
+        let sstables = [SSTable::flush_from_memtable(&dir, &flushed).unwrap()];
+
+        let mut memtable = MemTable::new();
+        memtable.set(b"bob", b"v1", 200);
+        memtable.set(b"dave", b"v1", 200);
+
+        let scan = Scan::new(&memtable, &sstables, Bound::Unbounded, Bound::Unbounded).unwrap();
+        let keys: Vec<Vec<u8>> = scan.map(|(key, _)| key).collect();
+        assert_eq!(
+            keys,
+            vec![b"alice".to_vec(), b"bob".to_vec(), b"carl".to_vec(), b"dave".to_vec()]
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_scan_prefers_newest_version_and_skips_tombstones() {
+        let dir = temp_dir("scan-shadow");
+
+        let mut flushed = MemTable::new();
+        flushed.set(b"alice", b"old", 100);
+        flushed.set(b"bob", b"old", 100);
+        let sstables = [SSTable::flush_from_memtable(&dir, &flushed).unwrap()];
+
+        let mut memtable = MemTable::new();
+        memtable.set(b"alice", b"new", 200);
+        memtable.delete(b"bob", 200);
+
+        let scan = Scan::new(&memtable, &sstables, Bound::Unbounded, Bound::Unbounded).unwrap();
+        let entries: Vec<(Vec<u8>, Vec<u8>)> = scan.collect();
+        assert_eq!(entries, vec![(b"alice".to_vec(), b"new".to_vec())]);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_scan_respects_lower_and_upper_bound() {
+        let dir = temp_dir("scan-bounds");
+
+        let mut memtable = MemTable::new();
+        memtable.set(b"alice", b"v1", 100);
+        memtable.set(b"bob", b"v1", 100);
+        memtable.set(b"carl", b"v1", 100);
+        memtable.set(b"dave", b"v1", 100);
+
+        let scan = Scan::new(
+            &memtable,
+            &[],
+            Bound::Included(b"bob".to_vec()),
+            Bound::Excluded(b"dave".to_vec()),
+        )
+        .unwrap();
+        let keys: Vec<Vec<u8>> = scan.map(|(key, _)| key).collect();
+        assert_eq!(keys, vec![b"bob".to_vec(), b"carl".to_vec()]);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,19 +1,603 @@
+#![allow(dead_code)]
+use std::{
+    fs::{File, OpenOptions},
+    io::{self, BufReader, BufWriter, Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+};
+
+use crate::{bloom::BloomFilter, get_current_timestamp, memtable::MemTable, memtable::MemTableEntry};
+
+#[cfg(feature = "mmap")]
+use std::sync::Arc;
+
+#[cfg(feature = "mmap")]
+use memmap2::Mmap;
+
 /// SSTABLE_MIN_SIZE specifies the number of records in a single SSTable.
-const SSTABLE_MIN_SIZE: usize = 1024;
+pub(crate) const SSTABLE_MIN_SIZE: usize = 1024;
+
+/// Size in bytes of the HEADER block (magic + version), and therefore the
+/// offset at which the DATA BLOCK begins.
+const SSTABLE_HEADER_SIZE: u64 = 4 + 4;
+
+/// Magic number identifying an SSTable file, written as the first 4 bytes
+/// of the HEADER block.
+const SSTABLE_MAGIC: u32 = 0x4B45_4753; // "KEGS"
+
+/// Current on-disk format version, written as the second 4 bytes of the
+/// HEADER block.
+const SSTABLE_VERSION: u32 = 1;
+
+/// Number of records between consecutive entries in the sparse INDEX BLOCK.
+const SSTABLE_INDEX_INTERVAL: usize = 16;
+
+/// Target false-positive rate for the per-table bloom filter.
+const SSTABLE_BLOOM_FALSE_POSITIVE_RATE: f64 = 0.01;
+
+/// Size in bytes of the fixed-size FOOTER written at the end of the file,
+/// it lets `SSTable::open` locate the INDEX and META blocks without
+/// scanning the whole file: `index offset (8B) | meta offset (8B) | magic (4B)`.
+const SSTABLE_FOOTER_SIZE: usize = 8 + 8 + 4;
+
+/// SSTable represents an on-disk MemTable, with keys and values laid out
+/// in order. SSTables are fixed size and when a level threshold is reached
+/// SSTables are compacted to a higher level.
+/// The compaction process frees up disk space by removing deleted key-value
+/// pairs.
+/// The file format used for SSTables is very simple :
+/// [HEADER][DATA BLOCK][INDEX BLOCK][META BLOCK][FOOTER]
+/// The [HEADER] holds a magic number and a version number.
+/// The [DATA BLOCK] is a segment of key-value pairs, encoded using the same
+/// layout as WAL records (key_len, tombstone, value_len, key, value, timestamp).
+/// The [INDEX BLOCK] is used as a hint file to create sparse indexes (key => offset),
+/// sampling one entry every `SSTABLE_INDEX_INTERVAL` records.
+/// The [META BLOCK] holds metadata about this SSTable such as the number of entries
+/// and lowest/highest key ranges in the SSTable.
+/// The [FOOTER] is a small fixed-size trailer pointing at the INDEX and META
+/// blocks so `open` can find them without a linear scan.
+///
+/// With the `mmap` feature enabled, the DATA BLOCK is additionally memory
+/// mapped once at construction time and `get` is served as a read against
+/// that mapping instead of a `File` seek + `read_exact`, avoiding a syscall
+/// and a buffer allocation per lookup. The FOOTER, META and INDEX blocks
+/// keep going through the regular file handle either way, since they are
+/// only read once. If the mapping can't be created (unsupported platform,
+/// or the feature is disabled) `get` transparently falls back to buffered
+/// `File` I/O.
+/// TODO: optimizations such as prefix encoding and compression.
+pub struct SSTable {
+    pub metadata: SSTableMetadata,
+    path: PathBuf,         // Path to the backing file, re-opened for independent scans.
+    file: BufReader<File>, // Physical file where the SSTable data is stored.
+    index: Vec<(Vec<u8>, u64)>, // Sparse index of (key => offset) loaded from the INDEX BLOCK.
+    data_end: u64,         // Offset of the first byte past the DATA BLOCK (start of INDEX BLOCK).
+    bloom: BloomFilter,    // Bloom filter of all keys in the SSTable, tested before `get` seeks.
+    #[cfg(feature = "mmap")]
+    data_map: Option<Arc<Mmap>>, // Zero-copy mapping of the DATA BLOCK, used by `get`/scans when present.
+}
+
+/// SSTableMetadata holds important metadata about an SSTable.
+pub struct SSTableMetadata {
+    pub id: u128,                   // Unique identifier for this SSTable (creation timestamp)
+    pub first_key: Vec<u8>,         // First key in this SSTable
+    pub last_key: Vec<u8>,          // Last key in this SSTable
+    pub total_size: usize,          // Total size of the table in bytes usually < 4MB
+    pub num_entries: usize,         // Number of unique key-value pairs in this SSTable.
+}
+
+impl SSTable {
+    /// Flush a `MemTable` to disk as a new SSTable in `dir`, returning a handle
+    /// to the freshly written file opened for reads.
+    ///
+    /// The MemTable's entries are assumed to already be sorted by key, they are
+    /// written as-is into the DATA BLOCK, a sparse INDEX BLOCK is built alongside
+    /// them, and a META BLOCK + FOOTER are appended once the data is flushed.
+    pub fn flush_from_memtable(dir: &Path, memtable: &MemTable) -> io::Result<SSTable> {
+        let id = get_current_timestamp();
+        let path = Path::new(dir).join(id.to_string() + ".sst");
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(&path)?;
+        let mut writer = BufWriter::new(file);
+
+        writer.write_all(&SSTABLE_MAGIC.to_le_bytes())?;
+        writer.write_all(&SSTABLE_VERSION.to_le_bytes())?;
+
+        let mut offset = SSTABLE_HEADER_SIZE;
+        let mut index = Vec::new();
+        let mut bloom = BloomFilter::new(
+            memtable.entries().len(),
+            SSTABLE_BLOOM_FALSE_POSITIVE_RATE,
+        );
+        for (i, entry) in memtable.entries().iter().enumerate() {
+            if i % SSTABLE_INDEX_INTERVAL == 0 {
+                index.push((entry.key.clone(), offset));
+            }
+            bloom.insert(&entry.key);
+            offset += write_entry(&mut writer, entry)? as u64;
+        }
+
+        let index_offset = offset;
+        write_index_block(&mut writer, &index)?;
+
+        let first_key = memtable
+            .entries()
+            .first()
+            .map(|e| e.key.clone())
+            .unwrap_or_default();
+        let last_key = memtable
+            .entries()
+            .last()
+            .map(|e| e.key.clone())
+            .unwrap_or_default();
+        let num_entries = memtable.entries().len();
+
+        let meta_offset = writer.stream_position()?;
+        writer.write_all(&(num_entries as u64).to_le_bytes())?;
+        write_bytes(&mut writer, &first_key)?;
+        write_bytes(&mut writer, &last_key)?;
+        write_bytes(&mut writer, &bloom.to_bytes())?;
+
+        writer.write_all(&index_offset.to_le_bytes())?;
+        writer.write_all(&meta_offset.to_le_bytes())?;
+        writer.write_all(&SSTABLE_MAGIC.to_le_bytes())?;
+        writer.flush()?;
+
+        let total_size = writer.stream_position()? as usize;
+
+        let metadata = SSTableMetadata {
+            id,
+            first_key,
+            last_key,
+            total_size,
+            num_entries,
+        };
+
+        let file = writer.into_inner().map_err(|e| e.into_error())?;
+        #[cfg(feature = "mmap")]
+        let data_map = mmap_data_block(&file, SSTABLE_HEADER_SIZE, index_offset).map(Arc::new);
+        let file = BufReader::new(file);
+
+        Ok(SSTable {
+            metadata,
+            path,
+            file,
+            index,
+            data_end: index_offset,
+            bloom,
+            #[cfg(feature = "mmap")]
+            data_map,
+        })
+    }
+
+    /// Open an existing SSTable from its file path, parsing the FOOTER, META
+    /// and INDEX blocks to rebuild an in-memory `SSTableMetadata` and sparse index.
+    pub fn open(path: &Path) -> io::Result<SSTable> {
+        let file = OpenOptions::new().read(true).open(path)?;
+        let mut reader = BufReader::new(file);
+
+        let total_size = reader.seek(SeekFrom::End(0))?;
+        reader.seek(SeekFrom::End(-(SSTABLE_FOOTER_SIZE as i64)))?;
+
+        let mut buf8 = [0; 8];
+        reader.read_exact(&mut buf8)?;
+        let index_offset = u64::from_le_bytes(buf8);
+        reader.read_exact(&mut buf8)?;
+        let meta_offset = u64::from_le_bytes(buf8);
 
-/// SSTable records are tuples of (key, key_length, value, value_length).
-pub struct SSTableRecord {
-    key: Vec<u8>,
-    size: usize,
-    value_offset: usize,
+        let mut magic_buf = [0; 4];
+        reader.read_exact(&mut magic_buf)?;
+        if u32::from_le_bytes(magic_buf) != SSTABLE_MAGIC {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "sstable: footer magic mismatch",
+            ));
+        }
+
+        reader.seek(SeekFrom::Start(meta_offset))?;
+        reader.read_exact(&mut buf8)?;
+        let num_entries = u64::from_le_bytes(buf8) as usize;
+        let first_key = read_bytes(&mut reader)?;
+        let last_key = read_bytes(&mut reader)?;
+        let bloom = BloomFilter::from_bytes(&read_bytes(&mut reader)?);
+
+        reader.seek(SeekFrom::Start(index_offset))?;
+        let index = read_index_block(&mut reader)?;
+
+        let metadata = SSTableMetadata {
+            id: path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .and_then(|s| s.parse().ok())
+                .unwrap_or_default(),
+            first_key,
+            last_key,
+            total_size: total_size as usize,
+            num_entries,
+        };
+
+        #[cfg(feature = "mmap")]
+        let data_map = mmap_data_block(reader.get_ref(), SSTABLE_HEADER_SIZE, index_offset).map(Arc::new);
+
+        Ok(SSTable {
+            metadata,
+            path: path.to_owned(),
+            file: reader,
+            index,
+            data_end: index_offset,
+            bloom,
+            #[cfg(feature = "mmap")]
+            data_map,
+        })
+    }
+
+    /// Path to the backing file on disk.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Iterate every record in the DATA BLOCK in key order, including
+    /// tombstones. Reads the mapped DATA BLOCK when `get`'s mmap path is
+    /// active; otherwise reopens the backing file so it doesn't disturb the
+    /// position used by `get`.
+    pub fn iter(&self) -> io::Result<SSTableEntryIter> {
+        #[cfg(feature = "mmap")]
+        if let Some(map) = &self.data_map {
+            return Ok(SSTableEntryIter::from_mmap(map.clone(), 0));
+        }
+
+        let file = OpenOptions::new().read(true).open(&self.path)?;
+        let mut reader = BufReader::new(file);
+        reader.seek(SeekFrom::Start(SSTABLE_HEADER_SIZE))?;
+        Ok(SSTableEntryIter::from_file(reader, self.data_end))
+    }
+
+    /// Iterate records in the DATA BLOCK starting at or before
+    /// `lower_bound`, using the sparse INDEX BLOCK to seek past most of the
+    /// file instead of scanning from the first record. Reads the mapped
+    /// DATA BLOCK when `get`'s mmap path is active; otherwise reopens the
+    /// backing file so it doesn't disturb the position used by `get`.
+    pub fn range(&self, lower_bound: &[u8]) -> io::Result<SSTableEntryIter> {
+        let start = match self.index.binary_search_by(|(k, _)| k.as_slice().cmp(lower_bound)) {
+            Ok(idx) => self.index[idx].1,
+            Err(0) => SSTABLE_HEADER_SIZE,
+            Err(idx) => self.index[idx - 1].1,
+        };
+
+        #[cfg(feature = "mmap")]
+        if let Some(map) = &self.data_map {
+            return Ok(SSTableEntryIter::from_mmap(map.clone(), (start - SSTABLE_HEADER_SIZE) as usize));
+        }
+
+        let file = OpenOptions::new().read(true).open(&self.path)?;
+        let mut reader = BufReader::new(file);
+        reader.seek(SeekFrom::Start(start))?;
+        Ok(SSTableEntryIter::from_file(reader, self.data_end))
+    }
+
+    /// Lookup a key in this SSTable. The bloom filter is tested first so
+    /// that keys it reports absent never pay for an index lookup or seek;
+    /// when it reports (possible) presence, the sparse INDEX BLOCK is binary
+    /// searched for the nearest offset at or before the key, then the DATA
+    /// BLOCK is scanned forward from there, as a slice read out of the
+    /// mapped DATA BLOCK when the `mmap` feature is enabled and the mapping
+    /// succeeded, or a buffered `File` read otherwise.
+    pub fn get(&mut self, key: &[u8]) -> io::Result<Option<MemTableEntry>> {
+        if !self.bloom.contains(key) {
+            return Ok(None);
+        }
+
+        let start = match self.index.binary_search_by(|(k, _)| k.as_slice().cmp(key)) {
+            Ok(idx) => self.index[idx].1,
+            Err(0) => return Ok(None),
+            Err(idx) => self.index[idx - 1].1,
+        };
+
+        #[cfg(feature = "mmap")]
+        if let Some(map) = &self.data_map {
+            let iter = SSTableEntryIter::from_mmap(map.clone(), (start - SSTABLE_HEADER_SIZE) as usize);
+            return Ok(find_in_iter(iter, key));
+        }
+
+        self.file.seek(SeekFrom::Start(start))?;
+        loop {
+            if self.file.stream_position()? >= self.data_end {
+                return Ok(None);
+            }
+            let entry = match read_entry(&mut self.file)? {
+                Some(entry) => entry,
+                None => return Ok(None),
+            };
+            match entry.key.as_slice().cmp(key) {
+                std::cmp::Ordering::Equal => return Ok(Some(entry)),
+                std::cmp::Ordering::Greater => return Ok(None),
+                std::cmp::Ordering::Less => continue,
+            }
+        }
+    }
+}
+
+/// Map the DATA BLOCK region (`[offset, offset + len)`) of `file` into
+/// memory, returning `None` rather than an error if the mapping can't be
+/// created so callers fall back to buffered `File` I/O instead of failing
+/// to open the SSTable on an unsupported platform.
+#[cfg(feature = "mmap")]
+fn mmap_data_block(file: &File, offset: u64, data_end: u64) -> Option<Mmap> {
+    let len = (data_end - offset) as usize;
+    // SAFETY: an SSTable's DATA BLOCK is written once by `flush_from_memtable`
+    // and never modified afterwards, so nothing else can mutate the mapped
+    // region out from under a reader for as long as this `SSTable` is alive.
+    unsafe {
+        memmap2::MmapOptions::new()
+            .offset(offset)
+            .len(len)
+            .map(file)
+            .ok()
+    }
+}
+
+/// Scan an already-positioned `SSTableEntryIter` forward for `key`, relying
+/// on key order to stop as soon as a greater key is seen.
+#[cfg(feature = "mmap")]
+fn find_in_iter(iter: SSTableEntryIter, key: &[u8]) -> Option<MemTableEntry> {
+    for entry in iter {
+        match entry.key.as_slice().cmp(key) {
+            std::cmp::Ordering::Equal => return Some(entry),
+            std::cmp::Ordering::Greater => return None,
+            std::cmp::Ordering::Less => continue,
+        }
+    }
+    None
+}
+
+/// Sequential iterator over an SSTable's DATA BLOCK, yielded by
+/// `SSTable::iter`/`SSTable::range`. Reads through a buffered `File` handle
+/// by default, or directly out of the mmap'd DATA BLOCK (no per-entry copy
+/// or syscall) when the `mmap` feature is enabled and the table was mapped
+/// successfully.
+pub struct SSTableEntryIter {
+    source: EntryIterSource,
+}
+
+enum EntryIterSource {
+    File { reader: BufReader<File>, end: u64 },
+    #[cfg(feature = "mmap")]
+    Mmap { map: Arc<Mmap>, pos: usize },
+}
+
+impl SSTableEntryIter {
+    fn from_file(reader: BufReader<File>, end: u64) -> SSTableEntryIter {
+        SSTableEntryIter {
+            source: EntryIterSource::File { reader, end },
+        }
+    }
+
+    #[cfg(feature = "mmap")]
+    fn from_mmap(map: Arc<Mmap>, pos: usize) -> SSTableEntryIter {
+        SSTableEntryIter {
+            source: EntryIterSource::Mmap { map, pos },
+        }
+    }
+}
+
+impl Iterator for SSTableEntryIter {
+    type Item = MemTableEntry;
+
+    fn next(&mut self) -> Option<MemTableEntry> {
+        match &mut self.source {
+            EntryIterSource::File { reader, end } => {
+                if reader.stream_position().ok()? >= *end {
+                    return None;
+                }
+                read_entry(reader).ok()?
+            }
+            #[cfg(feature = "mmap")]
+            EntryIterSource::Mmap { map, pos } => {
+                let mut cursor = &map[*pos..];
+                let before = cursor.len();
+                let entry = read_entry(&mut cursor).ok()??;
+                *pos += before - cursor.len();
+                Some(entry)
+            }
+        }
+    }
+}
+
+/// Write a single DATA BLOCK record using the same layout as WAL records,
+/// returning the number of bytes written.
+fn write_entry<W: Write>(writer: &mut W, entry: &MemTableEntry) -> io::Result<usize> {
+    let mut size = 0;
+    writer.write_all(&entry.key.len().to_le_bytes())?;
+    size += 8;
+    writer.write_all(&(entry.deleted as u8).to_le_bytes())?;
+    size += 1;
+    if !entry.deleted {
+        let value = entry.value.as_deref().unwrap_or_default();
+        writer.write_all(&value.len().to_le_bytes())?;
+        size += 8;
+        writer.write_all(&entry.key)?;
+        size += entry.key.len();
+        writer.write_all(value)?;
+        size += value.len();
+    } else {
+        writer.write_all(&entry.key)?;
+        size += entry.key.len();
+    }
+    writer.write_all(&entry.timestamp.to_le_bytes())?;
+    size += 16;
+    Ok(size)
+}
+
+/// Read a single DATA BLOCK record, returning `None` on EOF.
+fn read_entry<R: Read>(reader: &mut R) -> io::Result<Option<MemTableEntry>> {
+    let mut len_buf = [0; 8];
+    if reader.read_exact(&mut len_buf).is_err() {
+        return Ok(None);
+    }
+    let key_len = usize::from_le_bytes(len_buf);
+
+    let mut bool_buf = [0; 1];
+    reader.read_exact(&mut bool_buf)?;
+    let deleted = bool_buf[0] != 0;
+
+    let mut key = vec![0; key_len];
+    let value = if deleted {
+        reader.read_exact(&mut key)?;
+        None
+    } else {
+        reader.read_exact(&mut len_buf)?;
+        let value_len = usize::from_le_bytes(len_buf);
+        reader.read_exact(&mut key)?;
+        let mut value_buf = vec![0; value_len];
+        reader.read_exact(&mut value_buf)?;
+        Some(value_buf)
+    };
+
+    let mut ts_buf = [0; 16];
+    reader.read_exact(&mut ts_buf)?;
+    let timestamp = u128::from_le_bytes(ts_buf);
+
+    Ok(Some(MemTableEntry {
+        key,
+        value,
+        timestamp,
+        deleted,
+    }))
+}
+
+fn write_bytes<W: Write>(writer: &mut W, bytes: &[u8]) -> io::Result<()> {
+    writer.write_all(&bytes.len().to_le_bytes())?;
+    writer.write_all(bytes)
+}
+
+fn read_bytes<R: Read>(reader: &mut R) -> io::Result<Vec<u8>> {
+    let mut len_buf = [0; 8];
+    reader.read_exact(&mut len_buf)?;
+    let len = usize::from_le_bytes(len_buf);
+    let mut buf = vec![0; len];
+    reader.read_exact(&mut buf)?;
+    Ok(buf)
+}
+
+fn write_index_block<W: Write>(writer: &mut W, index: &[(Vec<u8>, u64)]) -> io::Result<()> {
+    writer.write_all(&(index.len() as u64).to_le_bytes())?;
+    for (key, offset) in index {
+        write_bytes(writer, key)?;
+        writer.write_all(&offset.to_le_bytes())?;
+    }
+    Ok(())
+}
+
+fn read_index_block<R: Read>(reader: &mut R) -> io::Result<Vec<(Vec<u8>, u64)>> {
+    let mut len_buf = [0; 8];
+    reader.read_exact(&mut len_buf)?;
+    let count = u64::from_le_bytes(len_buf) as usize;
+
+    let mut index = Vec::with_capacity(count);
+    for _ in 0..count {
+        let key = read_bytes(reader)?;
+        let mut offset_buf = [0; 8];
+        reader.read_exact(&mut offset_buf)?;
+        index.push((key, u64::from_le_bytes(offset_buf)));
+    }
+    Ok(index)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::temp_dir;
 
-    #[cfg(test)]
-    fn test_sstable_record() {
+    #[test]
+    fn test_sstable_min_size() {
         assert_eq!(SSTABLE_MIN_SIZE, 1024)
     }
+
+    #[test]
+    fn test_flush_and_get() {
+        let dir = temp_dir("sstable-flush");
+
+        let mut memtable = MemTable::new();
+        memtable.set(b"alice", b"product manager", get_current_timestamp());
+        memtable.set(b"bob", b"reliability engineer", get_current_timestamp());
+        memtable.set(b"carl", b"kernel engineer", get_current_timestamp());
+
+        let mut sstable = SSTable::flush_from_memtable(&dir, &memtable).unwrap();
+        assert_eq!(sstable.metadata.num_entries, 3);
+        assert_eq!(sstable.metadata.first_key, b"alice");
+        assert_eq!(sstable.metadata.last_key, b"carl");
+
+        let entry = sstable.get(b"bob").unwrap().unwrap();
+        assert_eq!(entry.value.unwrap(), b"reliability engineer");
+        assert!(sstable.get(b"dave").unwrap().is_none());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_range_seeks_to_indexed_entry_at_or_before_lower_bound() {
+        let dir = temp_dir("sstable-range-seek");
+
+        // A single index entry is sampled every `SSTABLE_INDEX_INTERVAL`
+        // records, so with only 3 entries `range` can only seek as far as
+        // the first record ("alice"); everything from there on is still
+        // yielded, including keys before the requested lower bound.
+        let mut memtable = MemTable::new();
+        memtable.set(b"alice", b"product manager", get_current_timestamp());
+        memtable.set(b"bob", b"reliability engineer", get_current_timestamp());
+        memtable.set(b"carl", b"kernel engineer", get_current_timestamp());
+
+        let sstable = SSTable::flush_from_memtable(&dir, &memtable).unwrap();
+
+        let keys: Vec<Vec<u8>> = sstable.range(b"bob").unwrap().map(|e| e.key).collect();
+        assert_eq!(keys, vec![b"alice".to_vec(), b"bob".to_vec(), b"carl".to_vec()]);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_open_reopens_flushed_table() {
+        let dir = temp_dir("sstable-reopen");
+
+        let mut memtable = MemTable::new();
+        memtable.set(b"alice", b"product manager", get_current_timestamp());
+
+        let written = SSTable::flush_from_memtable(&dir, &memtable).unwrap();
+
+        let mut reopened = SSTable::open(written.path()).unwrap();
+        let entry = reopened.get(b"alice").unwrap().unwrap();
+        assert_eq!(entry.value.unwrap(), b"product manager");
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(feature = "mmap")]
+    #[test]
+    fn test_mmap_backed_get_matches_buffered_reads() {
+        let dir = temp_dir("sstable-mmap");
+
+        let mut memtable = MemTable::new();
+        memtable.set(b"alice", b"product manager", get_current_timestamp());
+        memtable.set(b"bob", b"reliability engineer", get_current_timestamp());
+        memtable.set(b"carl", b"kernel engineer", get_current_timestamp());
+
+        let mut flushed = SSTable::flush_from_memtable(&dir, &memtable).unwrap();
+        assert!(flushed.data_map.is_some());
+
+        let entry = flushed.get(b"bob").unwrap().unwrap();
+        assert_eq!(entry.value.unwrap(), b"reliability engineer");
+        assert!(flushed.get(b"dave").unwrap().is_none());
+
+        let keys: Vec<Vec<u8>> = flushed.range(b"alice").unwrap().map(|e| e.key).collect();
+        assert_eq!(keys, vec![b"alice".to_vec(), b"bob".to_vec(), b"carl".to_vec()]);
+
+        let mut reopened = SSTable::open(flushed.path()).unwrap();
+        assert!(reopened.data_map.is_some());
+        assert_eq!(reopened.get(b"carl").unwrap().unwrap().value.unwrap(), b"kernel engineer");
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
 }